@@ -1,14 +1,32 @@
 use evtx::{EvtxParser, ParserSettings};
 use evtx::err::Result;
 use rayon::prelude::*;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fs::{File, OpenOptions, read_dir};
+use std::hash::{Hash, Hasher};
 use std::io::{Write, BufWriter, BufRead, BufReader};
 use std::path::Path;
 use std::sync::Mutex;
 use serde_xml_rs::from_str; // For XML deserialization
-use serde::Deserialize;
-use chrono::{NaiveDateTime, DateTime, Utc, TimeZone};
+use serde::{Deserialize, Serialize};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, DateTime, Duration, FixedOffset, Utc, TimeZone};
+
+/// Output encoding for matched events
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Re-emit the raw EVTX XML as-is (default, previous behavior)
+    Xml,
+    /// A single top-level JSON array of pretty-printed event objects
+    Json,
+    /// One compact JSON object per line (newline-delimited JSON)
+    Ndjson,
+    /// Fixed-column CSV with a header row: EventID, SystemTime, TargetUserName, ExtraData
+    Csv,
+    /// MessagePack-encoded `Event`, one record appended per match
+    Msgpack,
+}
 
 /// Command-line arguments structure
 #[derive(Parser, Debug)]
@@ -26,22 +44,84 @@ struct Args {
     #[arg(short, long)]
     users_file: Option<String>,
 
-    /// Start date for filtering logs (format: YYYY-MM-DD) (optional)
+    /// Start date for filtering logs: YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS, a relative
+    /// expression like -24h/-7d, or "now" (optional)
     #[arg(short = 's', long)]
     start_date: Option<String>,
 
-    /// End date for filtering logs (format: YYYY-MM-DD) (optional)
+    /// End date for filtering logs, inclusive of the whole day when no time is given:
+    /// YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS, a relative expression like -24h/-7d, or "now" (optional)
     #[arg(short = 'e', long)]
     end_date: Option<String>,
 
+    /// UTC offset applied to bare (timezone-less) dates, e.g. "+02:00" or "-0500" (optional, defaults to UTC)
+    #[arg(short = 'z', long)]
+    timezone: Option<String>,
+
     /// Optional number of threads (default is system maximum)
     #[arg(short, long, default_value_t = num_cpus::get())]
     threads: usize,
+
+    /// Output encoding for matched events: xml, json, ndjson, csv, msgpack
+    #[arg(long, value_enum, default_value = "xml")]
+    format: OutputFormat,
+
+    /// Print a per-EventID frequency report instead of writing matched events
+    #[arg(long, default_value_t = false)]
+    stats: bool,
+
+    /// Number of top users to list per EventID in the stats report
+    #[arg(long, default_value_t = 5)]
+    stats_top: usize,
+
+    /// Filter expression over EventID and named EventData entries (no other System field,
+    /// such as the provider, can be referenced), combining
+    /// comparisons with `and`/`or`/`not` and parentheses, e.g.
+    /// `(EventID == 4768 or EventID == 4769) and not ServiceName == "krbtgt"`.
+    /// When given, this replaces the built-in EVENT_IDS whitelist and --users-file check.
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// Deduplicate matched events across files (e.g. rotated logs) using a bounded age-set.
+    /// Takes an optional capacity for the "young" generation (default 10000).
+    #[arg(long, num_args = 0..=1, default_missing_value = "10000")]
+    dedup: Option<usize>,
 }
 
 // The Event IDs we want to include
 const EVENT_IDS: &[u16] = &[4624, 4625, 4768, 4769, 4776, 4672];
 
+/// The selection criteria shared by `process_evtx_file` and `process_evtx_file_stats`:
+/// the owned-users list, the resolved date range, and the optional `--filter` expression
+struct MatchCriteria<'a> {
+    owned_users: &'a [String],
+    start_date: Option<DateTime<Utc>>,
+    end_date: Option<DateTime<Utc>>,
+    filter: Option<&'a Filter>,
+}
+
+/// Decide whether an event should be selected, independent of the date-range check.
+///
+/// When a `--filter` expression is given it fully replaces the built-in `EVENT_IDS`
+/// whitelist and `--users-file` check below; otherwise those two keep their original behavior.
+fn matches_event_selection(event: &Event, owned_users: &[String], filter: Option<&Filter>) -> bool {
+    if let Some(filter) = filter {
+        return filter.matches(event);
+    }
+
+    if !EVENT_IDS.contains(&event.system.event_id) {
+        return false;
+    }
+
+    if owned_users.is_empty() {
+        return true;
+    }
+
+    event.event_data.data.iter()
+        .find(|data| data.name == "TargetUserName")
+        .is_some_and(|data| owned_users.contains(&data.value))
+}
+
 fn main() -> Result<()> {
     // Parse command-line arguments
     let args = Args::parse();
@@ -61,19 +141,90 @@ fn main() -> Result<()> {
         Vec::new()  // Empty list means all users
     };
 
-    // Parse the start and end dates (if provided)
-    let start_date = args.start_date.as_ref().map(|d| parse_date(d));
-    let end_date = args.end_date.as_ref().map(|d| parse_date(d));
+    // Resolve the optional --timezone offset applied to bare (timezone-less) dates
+    let timezone = match args.timezone.as_deref().map(parse_timezone_offset).transpose() {
+        Ok(timezone) => timezone,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // Parse the start and end dates (if provided); a bare --end-date is treated as end-of-day inclusive
+    let start_date = match args.start_date.as_ref().map(|d| parse_date(d, timezone.as_ref(), false)).transpose() {
+        Ok(start_date) => start_date,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let end_date = match args.end_date.as_ref().map(|d| parse_date(d, timezone.as_ref(), true)).transpose() {
+        Ok(end_date) => end_date,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // Parse the optional --filter expression once, up front
+    let filter = match args.filter.as_deref().map(Filter::parse).transpose() {
+        Ok(filter) => filter,
+        Err(err) => {
+            eprintln!("Error: invalid --filter expression: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let criteria = MatchCriteria {
+        owned_users: &owned_users,
+        start_date,
+        end_date,
+        filter: filter.as_ref(),
+    };
+
+    // A --stats run builds a frequency report instead of writing matched events out
+    if args.stats {
+        let input_path = Path::new(&args.input_path);
+        let report = if input_path.is_dir() {
+            let mut report = Report::default();
+            for entry in read_dir(input_path).expect("Failed to read directory") {
+                let entry = entry.expect("Failed to read entry");
+                let path = entry.path();
+                if path.extension().map_or(false, |ext| ext == "evtx") {
+                    let file_report = process_evtx_file_stats(path.to_str().unwrap(), &criteria);
+                    report = report.merge(file_report);
+                }
+            }
+            report
+        } else if input_path.is_file() {
+            process_evtx_file_stats(&args.input_path, &criteria)
+        } else {
+            println!("Invalid input path. Please provide a valid file or directory.");
+            Report::default()
+        };
+
+        print_stats_report(&report, args.stats_top);
+        return Ok(());
+    }
 
     println!("Writing matched events to output file: {}", args.output_file);
-    
+
     // Open the output file with a buffered writer for efficiency
     let output_file = OpenOptions::new()
         .create(true)
         .write(true)
         .truncate(true)
         .open(&args.output_file)?;
-    let output_writer = Mutex::new(BufWriter::new(output_file));
+    let mut output_writer = OutputWriter::new(BufWriter::new(output_file));
+    if args.format == OutputFormat::Json {
+        // Opening bracket of the top-level JSON array; closed once every file is processed
+        writeln!(output_writer.file, "[").unwrap();
+    }
+    let output_writer = Mutex::new(output_writer);
+
+    // Shared across every file processed, so duplicate records from rotated/overlapping
+    // .evtx files are only written once
+    let dedup = args.dedup.map(|capacity| Mutex::new(AgeSet::new(capacity)));
 
     // Check if the input path is a directory or a file
     let input_path = Path::new(&args.input_path);
@@ -83,24 +234,29 @@ fn main() -> Result<()> {
             let entry = entry.expect("Failed to read entry");
             let path = entry.path();
             if path.extension().map_or(false, |ext| ext == "evtx") {
-                process_evtx_file(path.to_str().unwrap(), &owned_users, start_date, end_date, &output_writer);
+                process_evtx_file(path.to_str().unwrap(), &criteria, dedup.as_ref(), &args.format, &output_writer);
             }
         }
     } else if input_path.is_file() {
         // If input is a single file, process the file
-        process_evtx_file(&args.input_path, &owned_users, start_date, end_date, &output_writer);
+        process_evtx_file(&args.input_path, &criteria, dedup.as_ref(), &args.format, &output_writer);
     } else {
         println!("Invalid input path. Please provide a valid file or directory.");
     }
 
+    if args.format == OutputFormat::Json {
+        let mut writer = output_writer.lock().unwrap();
+        writeln!(writer.file, "\n]").unwrap();
+    }
+
     println!("Processing complete. Check the output file for matched events.");
     Ok(())
 }
 
 /// Process a single .evtx file and append the results to the output
-fn process_evtx_file(evtx_file: &str, owned_users: &[String], start_date: Option<DateTime<Utc>>, end_date: Option<DateTime<Utc>>, output_writer: &Mutex<BufWriter<File>>) {
+fn process_evtx_file(evtx_file: &str, criteria: &MatchCriteria, dedup: Option<&Mutex<AgeSet>>, format: &OutputFormat, output_writer: &Mutex<OutputWriter>) {
     println!("Processing EVTX file: {}", evtx_file);
-    
+
     // Open the EVTX file
     let mut parser = EvtxParser::from_path(evtx_file).expect("Failed to open EVTX file");
 
@@ -113,32 +269,35 @@ fn process_evtx_file(evtx_file: &str, owned_users: &[String], start_date: Option
                     // Get the XML data from the record
                     let xml_output = record.data.clone();  // Clone the entire XML
 
-                    // Only process the record if its EventID matches one of the ones we care about
-                    if let Some(event_id) = get_event_id_from_xml(&xml_output) {
-                        if EVENT_IDS.contains(&event_id) {
-                            // Get the event timestamp
-                            if let Some(event_time) = get_time_created_from_xml(&xml_output) {
-                                // Check if the event falls within the specified date range
-                                if in_date_range(&event_time, start_date, end_date) {
-                                    // Check if we need to filter by TargetUserName
-                                    if owned_users.is_empty() {
-                                        // No user file provided, write the full XML event
-                                        let mut writer = output_writer.lock().unwrap();
-                                        writeln!(writer, "{}", xml_output).unwrap();
-                                    } else {
-                                        // User file is provided, filter by TargetUserName
-                                        if let Some(target_user_name) = get_target_user_name_from_xml(&xml_output) {
-                                            if owned_users.contains(&target_user_name) {
-                                                // Write the matched XML event
-                                                let mut writer = output_writer.lock().unwrap();
-                                                writeln!(writer, "{}", xml_output).unwrap();
-                                            }
-                                        }
-                                    }
-                                }
-                            }
+                    // Deserialize once and reuse the same `Event` for filtering and output
+                    let event: Event = match from_str(&xml_output) {
+                        Ok(event) => event,
+                        Err(_) => return,
+                    };
+
+                    if !matches_event_selection(&event, criteria.owned_users, criteria.filter) {
+                        return;
+                    }
+
+                    let event_time = match event.system.time_created.as_ref().and_then(|tc| parse_time_created(&tc.system_time)) {
+                        Some(event_time) => event_time,
+                        None => return,
+                    };
+
+                    // Check if the event falls within the specified date range
+                    if !in_date_range(&event_time, criteria.start_date, criteria.end_date) {
+                        return;
+                    }
+
+                    // Drop events already seen (possibly in an earlier, overlapping file)
+                    if let Some(dedup) = dedup {
+                        let fingerprint = event_fingerprint(record.event_record_id, &event_time, event.system.event_id);
+                        if !dedup.lock().unwrap().insert(fingerprint) {
+                            return;
                         }
                     }
+
+                    write_event(&xml_output, &event, format, output_writer);
                 }
                 Err(err) => {
                     eprintln!("Error processing record: {}", err);
@@ -147,36 +306,547 @@ fn process_evtx_file(evtx_file: &str, owned_users: &[String], start_date: Option
         });
 }
 
-/// Function to get the EventID from the XML string
-fn get_event_id_from_xml(xml_str: &str) -> Option<u16> {
-    // Deserialize the XML and extract the EventID
-    if let Ok(event) = from_str::<Event>(xml_str) {
-        Some(event.system.event_id)
-    } else {
-        None
+/// The shared output file plus the bits of encoding state that span multiple writes:
+/// whether the top-level JSON array has its first element yet, and whether the CSV
+/// header row has already gone out
+struct OutputWriter {
+    file: BufWriter<File>,
+    json_started: bool,
+    csv_header_written: bool,
+}
+
+impl OutputWriter {
+    fn new(file: BufWriter<File>) -> Self {
+        OutputWriter {
+            file,
+            json_started: false,
+            csv_header_written: false,
+        }
     }
 }
 
-/// Function to get the TargetUserName from the XML string
-fn get_target_user_name_from_xml(xml_str: &str) -> Option<String> {
-    if let Ok(event) = from_str::<Event>(xml_str) {
-        for data in event.event_data.data {
-            if data.name == "TargetUserName" {
-                return Some(data.value);
+/// Re-encode a matched event into the requested `OutputFormat` and append it to `output_writer`
+fn write_event(xml_output: &str, event: &Event, format: &OutputFormat, output_writer: &Mutex<OutputWriter>) {
+    match format {
+        OutputFormat::Xml => {
+            let mut writer = output_writer.lock().unwrap();
+            writeln!(writer.file, "{}", xml_output).unwrap();
+        }
+        OutputFormat::Json => {
+            // One element of the top-level array `main` opens before, and closes after, processing
+            let json = serde_json::to_string_pretty(event).expect("Failed to serialize event to JSON");
+            let mut writer = output_writer.lock().unwrap();
+            if writer.json_started {
+                write!(writer.file, ",\n{}", json).unwrap();
+            } else {
+                write!(writer.file, "{}", json).unwrap();
+                writer.json_started = true;
             }
         }
+        OutputFormat::Ndjson => {
+            let json = serde_json::to_string(event).expect("Failed to serialize event to NDJSON");
+            let mut writer = output_writer.lock().unwrap();
+            writeln!(writer.file, "{}", json).unwrap();
+        }
+        OutputFormat::Csv => {
+            let record = CsvRecord::from_event(event);
+            let mut writer = output_writer.lock().unwrap();
+            let write_header = !writer.csv_header_written;
+            let mut csv_writer = csv::WriterBuilder::new().has_headers(write_header).from_writer(vec![]);
+            csv_writer.serialize(&record).expect("Failed to serialize event to CSV");
+            let bytes = csv_writer.into_inner().expect("Failed to flush CSV writer");
+            writer.file.write_all(&bytes).unwrap();
+            writer.csv_header_written = true;
+        }
+        OutputFormat::Msgpack => {
+            let bytes = rmp_serde::to_vec(event).expect("Failed to serialize event to MessagePack");
+            let mut writer = output_writer.lock().unwrap();
+            writer.file.write_all(&bytes).unwrap();
+        }
     }
-    None
 }
 
-/// Function to get the TimeCreated from the XML string
-fn get_time_created_from_xml(xml_str: &str) -> Option<DateTime<Utc>> {
-    if let Ok(event) = from_str::<Event>(xml_str) {
-        if let Some(system_time) = event.system.time_created {
-            return parse_time_created(&system_time.system_time);
+/// Process a single .evtx file, accumulating a frequency `Report` instead of writing events out.
+///
+/// Each rayon worker folds matched records into its own local `Report`; the locals are then
+/// merged pairwise via `reduce`, so no lock is held per record the way `output_writer` is.
+fn process_evtx_file_stats(evtx_file: &str, criteria: &MatchCriteria) -> Report {
+    println!("Processing EVTX file: {}", evtx_file);
+
+    let mut parser = EvtxParser::from_path(evtx_file).expect("Failed to open EVTX file");
+
+    parser.records()
+        .par_bridge()
+        .fold(Report::default, |mut report, record| {
+            let record = match record {
+                Ok(record) => record,
+                Err(err) => {
+                    eprintln!("Error processing record: {}", err);
+                    return report;
+                }
+            };
+
+            let xml_output = record.data;
+            let event: Event = match from_str(&xml_output) {
+                Ok(event) => event,
+                Err(_) => return report,
+            };
+
+            if !matches_event_selection(&event, criteria.owned_users, criteria.filter) {
+                return report;
+            }
+
+            let event_time = match event.system.time_created.as_ref().and_then(|tc| parse_time_created(&tc.system_time)) {
+                Some(event_time) => event_time,
+                None => return report,
+            };
+
+            if !in_date_range(&event_time, criteria.start_date, criteria.end_date) {
+                return report;
+            }
+
+            let target_user_name = event.event_data.data.iter().find(|data| data.name == "TargetUserName").map(|data| data.value.as_str());
+
+            report.record(event.system.event_id, target_user_name, event_time);
+            report
+        })
+        .reduce(Report::default, Report::merge)
+}
+
+/// Per-EventID frequency counts accumulated by `--stats` mode
+#[derive(Default)]
+struct Report {
+    /// (EventID, TargetUserName) -> occurrence count
+    by_event_and_user: HashMap<(u16, String), u64>,
+    /// EventID -> total occurrence count
+    totals: HashMap<u16, u64>,
+    /// EventID -> (earliest, latest) event timestamp observed
+    time_span: HashMap<u16, (DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl Report {
+    /// Fold a single matched event into this report
+    fn record(&mut self, event_id: u16, target_user_name: Option<&str>, event_time: DateTime<Utc>) {
+        *self.totals.entry(event_id).or_insert(0) += 1;
+
+        let user_key = target_user_name.unwrap_or("").to_string();
+        *self.by_event_and_user.entry((event_id, user_key)).or_insert(0) += 1;
+
+        self.time_span.entry(event_id)
+            .and_modify(|(min, max)| {
+                if event_time < *min { *min = event_time; }
+                if event_time > *max { *max = event_time; }
+            })
+            .or_insert((event_time, event_time));
+    }
+
+    /// Merge another report's counts into this one, used to combine per-worker and per-file locals
+    fn merge(mut self, other: Report) -> Self {
+        for (key, count) in other.by_event_and_user {
+            *self.by_event_and_user.entry(key).or_insert(0) += count;
+        }
+        for (event_id, count) in other.totals {
+            *self.totals.entry(event_id).or_insert(0) += count;
+        }
+        for (event_id, (min, max)) in other.time_span {
+            self.time_span.entry(event_id)
+                .and_modify(|(cur_min, cur_max)| {
+                    if min < *cur_min { *cur_min = min; }
+                    if max > *cur_max { *cur_max = max; }
+                })
+                .or_insert((min, max));
+        }
+        self
+    }
+}
+
+/// Print the accumulated `Report` as a per-EventID triage summary
+fn print_stats_report(report: &Report, top_n: usize) {
+    println!("\n=== Event Statistics Report ===");
+
+    let mut event_ids: Vec<&u16> = report.totals.keys().collect();
+    event_ids.sort();
+
+    for event_id in event_ids {
+        let total = report.totals[event_id];
+        let (min, max) = report.time_span[event_id];
+
+        let mut users: Vec<(&str, u64)> = report.by_event_and_user.iter()
+            .filter(|((id, _), _)| id == event_id)
+            .map(|((_, user), count)| (user.as_str(), *count))
+            .collect();
+        users.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        println!("\nEventID {}: {} occurrence(s)", event_id, total);
+        println!("  Time span: {} -> {}", min, max);
+        println!("  Distinct users: {}", users.len());
+        println!("  Top {} users:", top_n);
+        for (user, count) in users.iter().take(top_n) {
+            let label = if user.is_empty() { "(none)" } else { user };
+            println!("    {}: {}", label, count);
+        }
+    }
+}
+
+/// A bounded-memory "seen before" set for cross-file dedup, built from two `HashSet`
+/// generations ("young" and "old") instead of one ever-growing set.
+///
+/// Every candidate fingerprint is checked against both generations. New fingerprints are
+/// added to "young"; once "young" outgrows `capacity`, "old" is discarded, "young" is
+/// promoted to "old", and a fresh "young" is started. This gives O(1) amortized lookups
+/// with memory bounded by roughly 2x `capacity`, regardless of total event count.
+struct AgeSet {
+    young: HashSet<u64>,
+    old: HashSet<u64>,
+    capacity: usize,
+}
+
+impl AgeSet {
+    fn new(capacity: usize) -> Self {
+        AgeSet {
+            young: HashSet::new(),
+            old: HashSet::new(),
+            capacity,
+        }
+    }
+
+    /// Insert `fingerprint` if it hasn't been seen in either generation.
+    /// Returns `true` when it was new (and so should be processed), `false` for a duplicate.
+    fn insert(&mut self, fingerprint: u64) -> bool {
+        if self.young.contains(&fingerprint) || self.old.contains(&fingerprint) {
+            return false;
+        }
+
+        self.young.insert(fingerprint);
+        if self.young.len() > self.capacity {
+            self.old = std::mem::take(&mut self.young);
+        }
+        true
+    }
+}
+
+/// Fingerprint a matched event from its EventRecordID, timestamp, and EventID, for use with `AgeSet`
+fn event_fingerprint(event_record_id: u64, event_time: &DateTime<Utc>, event_id: u16) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    event_record_id.hash(&mut hasher);
+    event_time.hash(&mut hasher);
+    event_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A parsed `--filter` expression, evaluated against a deserialized `Event`.
+///
+/// Grammar (case-insensitive keywords `and`/`or`/`not`/`in`, `and` binds tighter than `or`):
+///   filter     := or_expr
+///   or_expr    := and_expr ("or" and_expr)*
+///   and_expr   := term ("and" term)*
+///   term       := ["not"] atom
+///   atom       := comparison | "(" or_expr ")"
+///   comparison := field "==" value
+///               | field "!=" value
+///               | field "in" "{" value ("," value)* "}"
+///   field      := "EventID" | <EventData Name>
+///   value      := '"' ... '"' | bare-word
+struct Filter {
+    root: Predicate,
+}
+
+impl Filter {
+    /// Parse a `--filter` expression into a `Filter`
+    fn parse(expr: &str) -> std::result::Result<Filter, String> {
+        let tokens = tokenize(expr)?;
+        let mut pos = 0;
+        let root = parse_or(&tokens, &mut pos)?;
+        if pos != tokens.len() {
+            return Err(format!("Unexpected trailing input near token {}", pos));
+        }
+        Ok(Filter { root })
+    }
+
+    /// Evaluate this filter against a deserialized event
+    fn matches(&self, event: &Event) -> bool {
+        self.root.matches(event)
+    }
+}
+
+/// A field referenced on the left-hand side of a filter comparison
+enum Field {
+    EventId,
+    Named(String),
+}
+
+impl Field {
+    fn value_of(&self, event: &Event) -> Option<String> {
+        match self {
+            Field::EventId => Some(event.system.event_id.to_string()),
+            Field::Named(name) => event.event_data.data.iter()
+                .find(|data| &data.name == name)
+                .map(|data| data.value.clone()),
+        }
+    }
+}
+
+/// The filter AST: a tree of field comparisons combined with `and`/`or`/`not`, with
+/// parenthesized grouping handled entirely in the parser (no separate `Group` node needed)
+enum Predicate {
+    Eq(Field, String),
+    Ne(Field, String),
+    In(Field, Vec<String>),
+    Not(Box<Predicate>),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+}
+
+impl Predicate {
+    fn matches(&self, event: &Event) -> bool {
+        match self {
+            Predicate::Eq(field, value) => field.value_of(event).as_deref() == Some(value.as_str()),
+            Predicate::Ne(field, value) => field.value_of(event).as_deref() != Some(value.as_str()),
+            Predicate::In(field, values) => field.value_of(event).is_some_and(|v| values.contains(&v)),
+            Predicate::Not(inner) => !inner.matches(event),
+            Predicate::And(lhs, rhs) => lhs.matches(event) && rhs.matches(event),
+            Predicate::Or(lhs, rhs) => lhs.matches(event) || rhs.matches(event),
+        }
+    }
+}
+
+/// A single lexical token of a `--filter` expression
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Eq,
+    Ne,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// Split a `--filter` expression into tokens, honoring `"..."` quoted strings
+fn tokenize(expr: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        match ch {
+            ' ' | '\t' | '\n' | '\r' => {
+                chars.next();
+            }
+            '{' => {
+                chars.next();
+                tokens.push(Token::LBrace);
+            }
+            '}' => {
+                chars.next();
+                tokens.push(Token::RBrace);
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            ',' => {
+                chars.next();
+                tokens.push(Token::Comma);
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => value.push(c),
+                        None => return Err("Unterminated string literal".to_string()),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            '=' => {
+                chars.next();
+                if chars.next() != Some('=') {
+                    return Err("Expected '==', found a single '='".to_string());
+                }
+                tokens.push(Token::Eq);
+            }
+            '!' => {
+                chars.next();
+                if chars.next() != Some('=') {
+                    return Err("Expected '!=', found a single '!'".to_string());
+                }
+                tokens.push(Token::Ne);
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '{' | '}' | '(' | ')' | ',' | '=' | '!' | '"') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_or(tokens: &[Token], pos: &mut usize) -> std::result::Result<Predicate, String> {
+    let mut lhs = parse_and(tokens, pos)?;
+    while matches!(tokens.get(*pos), Some(Token::Word(word)) if word.eq_ignore_ascii_case("or")) {
+        *pos += 1;
+        let rhs = parse_and(tokens, pos)?;
+        lhs = Predicate::Or(Box::new(lhs), Box::new(rhs));
+    }
+    Ok(lhs)
+}
+
+fn parse_and(tokens: &[Token], pos: &mut usize) -> std::result::Result<Predicate, String> {
+    let mut lhs = parse_term(tokens, pos)?;
+    while matches!(tokens.get(*pos), Some(Token::Word(word)) if word.eq_ignore_ascii_case("and")) {
+        *pos += 1;
+        let rhs = parse_term(tokens, pos)?;
+        lhs = Predicate::And(Box::new(lhs), Box::new(rhs));
+    }
+    Ok(lhs)
+}
+
+fn parse_term(tokens: &[Token], pos: &mut usize) -> std::result::Result<Predicate, String> {
+    if matches!(tokens.get(*pos), Some(Token::Word(word)) if word.eq_ignore_ascii_case("not")) {
+        *pos += 1;
+        return Ok(Predicate::Not(Box::new(parse_atom(tokens, pos)?)));
+    }
+    parse_atom(tokens, pos)
+}
+
+/// A comparison, or a fully parenthesized sub-expression
+fn parse_atom(tokens: &[Token], pos: &mut usize) -> std::result::Result<Predicate, String> {
+    if tokens.get(*pos) == Some(&Token::LParen) {
+        *pos += 1;
+        let inner = parse_or(tokens, pos)?;
+        match tokens.get(*pos) {
+            Some(Token::RParen) => *pos += 1,
+            other => return Err(format!("Expected ')', found {:?}", other)),
+        }
+        return Ok(inner);
+    }
+    parse_comparison(tokens, pos)
+}
+
+fn parse_comparison(tokens: &[Token], pos: &mut usize) -> std::result::Result<Predicate, String> {
+    let field = match tokens.get(*pos) {
+        Some(Token::Word(name)) => {
+            *pos += 1;
+            if name.eq_ignore_ascii_case("EventID") {
+                Field::EventId
+            } else {
+                Field::Named(name.clone())
+            }
+        }
+        other => return Err(format!("Expected a field name, found {:?}", other)),
+    };
+
+    match tokens.get(*pos) {
+        Some(Token::Eq) => {
+            *pos += 1;
+            let value = parse_value(tokens, pos)?;
+            Ok(Predicate::Eq(field, value))
+        }
+        Some(Token::Ne) => {
+            *pos += 1;
+            let value = parse_value(tokens, pos)?;
+            Ok(Predicate::Ne(field, value))
+        }
+        Some(Token::Word(word)) if word.eq_ignore_ascii_case("in") => {
+            *pos += 1;
+            let values = parse_value_set(tokens, pos)?;
+            Ok(Predicate::In(field, values))
+        }
+        other => Err(format!("Expected '==', '!=', or 'in', found {:?}", other)),
+    }
+}
+
+fn parse_value(tokens: &[Token], pos: &mut usize) -> std::result::Result<String, String> {
+    match tokens.get(*pos) {
+        Some(Token::Word(word)) => {
+            *pos += 1;
+            Ok(word.clone())
+        }
+        Some(Token::Str(value)) => {
+            *pos += 1;
+            Ok(value.clone())
+        }
+        other => Err(format!("Expected a value, found {:?}", other)),
+    }
+}
+
+fn parse_value_set(tokens: &[Token], pos: &mut usize) -> std::result::Result<Vec<String>, String> {
+    if tokens.get(*pos) != Some(&Token::LBrace) {
+        return Err(format!("Expected '{{', found {:?}", tokens.get(*pos)));
+    }
+    *pos += 1;
+
+    let mut values = Vec::new();
+    loop {
+        values.push(parse_value(tokens, pos)?);
+        match tokens.get(*pos) {
+            Some(Token::Comma) => {
+                *pos += 1;
+            }
+            Some(Token::RBrace) => {
+                *pos += 1;
+                break;
+            }
+            other => return Err(format!("Expected ',' or '}}', found {:?}", other)),
+        }
+    }
+
+    Ok(values)
+}
+
+/// Fixed four-column CSV projection of an `Event`, used by `OutputFormat::Csv`. EventData is
+/// dynamic per-record, so rather than varying the column count per row, every field besides
+/// `TargetUserName` is joined into the single `extra_data` column as `Name=Value` pairs.
+#[derive(Serialize)]
+struct CsvRecord {
+    #[serde(rename = "EventID")]
+    event_id: u16,
+    #[serde(rename = "SystemTime")]
+    system_time: String,
+    #[serde(rename = "TargetUserName")]
+    target_user_name: String,
+    #[serde(rename = "ExtraData")]
+    extra_data: String,
+}
+
+impl CsvRecord {
+    /// Project an `Event` into the fixed CSV columns, joining any remaining
+    /// EventData fields (besides `TargetUserName`) into `extra_data` as `Name=Value` pairs
+    fn from_event(event: &Event) -> Self {
+        let system_time = event.system.time_created.as_ref().map(|tc| tc.system_time.clone()).unwrap_or_default();
+        let mut target_user_name = String::new();
+        let mut extra_data = Vec::new();
+        for data in &event.event_data.data {
+            if data.name == "TargetUserName" {
+                target_user_name = data.value.clone();
+            } else {
+                extra_data.push(format!("{}={}", data.name, data.value));
+            }
+        }
+
+        CsvRecord {
+            event_id: event.system.event_id,
+            system_time,
+            target_user_name,
+            extra_data: extra_data.join(";"),
         }
     }
-    None
 }
 
 /// Check if the event timestamp falls within the provided date range
@@ -194,10 +864,82 @@ fn in_date_range(event_time: &DateTime<Utc>, start: Option<DateTime<Utc>>, end:
     true
 }
 
-/// Parse a date in the format YYYY-MM-DD
-fn parse_date(date_str: &str) -> DateTime<Utc> {
-    Utc.datetime_from_str(&format!("{} 00:00:00", date_str), "%Y-%m-%d %H:%M:%S")
-        .expect("Invalid date format. Use YYYY-MM-DD.")
+/// Parse a `--start-date`/`--end-date` value.
+///
+/// Accepts a full timestamp (`YYYY-MM-DD` or `YYYY-MM-DDTHH:MM:SS`, localized against
+/// `timezone` when given, UTC otherwise), a relative expression like `-24h`/`-7d` resolved
+/// against `Utc::now()`, or the literal `now`. When `end_of_day` is set, a bare date with no
+/// time component snaps to `23:59:59.999` so the end date is inclusive of the whole day.
+fn parse_date(date_str: &str, timezone: Option<&FixedOffset>, end_of_day: bool) -> std::result::Result<DateTime<Utc>, String> {
+    let trimmed = date_str.trim();
+
+    if trimmed.eq_ignore_ascii_case("now") {
+        return Ok(Utc::now());
+    }
+
+    if let Some(duration) = parse_relative_duration(trimmed) {
+        return Ok(Utc::now() - duration);
+    }
+
+    let naive = if let Ok(dt) = NaiveDateTime::parse_from_str(trimmed, "%Y-%m-%dT%H:%M:%S") {
+        dt
+    } else if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        let time = if end_of_day {
+            NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap()
+        } else {
+            NaiveTime::from_hms_opt(0, 0, 0).unwrap()
+        };
+        date.and_time(time)
+    } else {
+        return Err(format!(
+            "Invalid date '{}'. Expected YYYY-MM-DD, YYYY-MM-DDTHH:MM:SS, a relative expression like -24h/-7d, or 'now'.",
+            date_str
+        ));
+    };
+
+    let offset = timezone.copied().unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+    offset.from_local_datetime(&naive)
+        .single()
+        .map(|localized| localized.with_timezone(&Utc))
+        .ok_or_else(|| format!("Ambiguous or invalid local time for '{}' in the given timezone.", date_str))
+}
+
+/// Parse a relative offset like `-24h`, `-7d`, `-30m`, or `-45s` into a `Duration`.
+/// Returns `None` if `expr` isn't a relative expression, so callers can fall through
+/// to absolute-date parsing.
+fn parse_relative_duration(expr: &str) -> Option<Duration> {
+    let rest = expr.strip_prefix('-')?;
+    let unit = rest.chars().last()?;
+    let amount: i64 = rest[..rest.len() - unit.len_utf8()].parse().ok()?;
+
+    match unit {
+        's' => Some(Duration::seconds(amount)),
+        'm' => Some(Duration::minutes(amount)),
+        'h' => Some(Duration::hours(amount)),
+        'd' => Some(Duration::days(amount)),
+        _ => None,
+    }
+}
+
+/// Parse a `--timezone` UTC offset like `+02:00`, `-0500`, or `+0200`
+fn parse_timezone_offset(tz: &str) -> std::result::Result<FixedOffset, String> {
+    let tz = tz.trim();
+    let (sign, digits) = if let Some(rest) = tz.strip_prefix('-') {
+        (-1, rest)
+    } else if let Some(rest) = tz.strip_prefix('+') {
+        (1, rest)
+    } else {
+        (1, tz)
+    };
+    let digits = digits.replace(':', "");
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid timezone offset '{}'. Expected a format like +02:00 or -0500.", tz));
+    }
+
+    let hours: i32 = digits[0..2].parse().unwrap();
+    let minutes: i32 = digits[2..4].parse().unwrap();
+    let seconds = sign * (hours * 3600 + minutes * 60);
+    FixedOffset::east_opt(seconds).ok_or_else(|| format!("Timezone offset '{}' is out of range.", tz))
 }
 
 /// Parse the TimeCreated field from the XML into a DateTime<Utc>
@@ -209,7 +951,7 @@ fn parse_time_created(time_str: &str) -> Option<DateTime<Utc>> {
 }
 
 /// Structure to represent the Event XML
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct Event {
     #[serde(rename = "System")]
     system: System,
@@ -219,7 +961,7 @@ struct Event {
 }
 
 /// Structure to represent the System element in the Event XML
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct System {
     #[serde(rename = "EventID")]
     event_id: u16,
@@ -229,21 +971,21 @@ struct System {
 }
 
 /// Structure to represent the TimeCreated element in the System XML
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct TimeCreated {
     #[serde(rename = "SystemTime")]
     system_time: String,
 }
 
 /// Structure to represent the EventData element in the Event XML
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct EventData {
     #[serde(rename = "Data", default)]
     data: Vec<Data>,
 }
 
 /// Structure to represent individual Data elements in EventData
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct Data {
     #[serde(rename = "Name", default)]
     name: String,